@@ -1,235 +1,613 @@
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
 use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::io;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
 
-/// Узел AVL-дерева.
+/// Индекс "пустого" узла в пуле.
+const NULL: u32 = u32::MAX;
+
+/// Узел AVL-дерева, хранящийся в пуле `AVLTree::pool`.
+///
+/// Вместо `Option<Box<Node>>` дочерние узлы адресуются индексами `u32` в
+/// пуле — это убирает аллокацию на каждый узел и делает хранилище
+/// непрерывным и дружелюбным к кэшу.
 #[derive(Debug)]
 struct Node<K, V> {
     key: K,
     value: V,
-    height: usize,
-    left: Option<Box<Node<K, V>>>,
-    right: Option<Box<Node<K, V>>>,
+    height: u32,
+    size: u32,
+    left: u32,
+    right: u32,
 }
 
-impl<K: Ord + Debug, V: Debug> Node<K, V> {
-    /// Создает новый узел.
+impl<K, V> Node<K, V> {
+    /// Создает новый узел-лист.
     fn new(key: K, value: V) -> Self {
         Node {
             key,
             value,
             height: 1,
-            left: None,
-            right: None,
+            size: 1,
+            left: NULL,
+            right: NULL,
         }
     }
+}
+
+/// Структура AVL-дерева.
+///
+/// Узлы живут в едином пуле `pool`; освобожденные при удалении слоты
+/// переиспользуются через `free` вместо того, чтобы пул рос бесконечно.
+pub struct AVLTree<K, V> {
+    pool: Vec<Option<Node<K, V>>>,
+    free: Vec<u32>,
+    root: u32,
+}
 
-    /// Получает высоту узла.
-    fn height(node: &Option<Box<Node<K, V>>>) -> usize {
-        match node {
-            Some(n) => n.height,
-            None => 0,
+impl<K: Ord + Debug, V: Debug> AVLTree<K, V> {
+    /// Создает новое пустое AVL-дерево.
+    pub fn new() -> Self {
+        AVLTree {
+            pool: Vec::new(),
+            free: Vec::new(),
+            root: NULL,
         }
     }
 
-    /// Обновляет высоту узла.
-    fn update_height(&mut self) {
-        let left_height = Self::height(&self.left);
-        let right_height = Self::height(&self.right);
-        self.height = 1 + std::cmp::max(left_height, right_height);
+    /// Возвращает ссылку на узел по индексу.
+    fn node(&self, idx: u32) -> &Node<K, V> {
+        self.pool[idx as usize]
+            .as_ref()
+            .expect("индекс должен указывать на занятый слот пула")
     }
 
-    /// Баланс-фактор узла.
-    fn balance_factor(&self) -> isize {
-        let left_height = Self::height(&self.left) as isize;
-        let right_height = Self::height(&self.right) as isize;
-        left_height - right_height
+    /// Возвращает изменяемую ссылку на узел по индексу.
+    fn node_mut(&mut self, idx: u32) -> &mut Node<K, V> {
+        self.pool[idx as usize]
+            .as_mut()
+            .expect("индекс должен указывать на занятый слот пула")
     }
 
-    /// Выполняет правый поворот.
-    fn rotate_right(mut self: Box<Self>) -> Box<Self> {
-        let mut new_root = self
-            .left
+    /// Выделяет слот в пуле под новый узел, переиспользуя освобожденные
+    /// индексы из `free`, и возвращает его индекс.
+    fn alloc(&mut self, key: K, value: V) -> u32 {
+        let node = Node::new(key, value);
+        if let Some(idx) = self.free.pop() {
+            self.pool[idx as usize] = Some(node);
+            idx
+        } else {
+            self.pool.push(Some(node));
+            (self.pool.len() - 1) as u32
+        }
+    }
+
+    /// Забирает узел из пула по владению, помечая слот свободным для
+    /// переиспользования.
+    fn take_node(&mut self, idx: u32) -> Node<K, V> {
+        let node = self.pool[idx as usize]
             .take()
-            .expect("Левый дочерний узел должен существовать");
-        self.left = new_root.right.take();
-        self.update_height();
-        new_root.right = Some(self);
-        new_root.update_height();
+            .expect("индекс должен указывать на занятый слот пула");
+        self.free.push(idx);
+        node
+    }
+
+    /// Получает высоту поддерева по индексу корня (0 для `NULL`).
+    fn height(&self, idx: u32) -> u32 {
+        if idx == NULL {
+            0
+        } else {
+            self.node(idx).height
+        }
+    }
+
+    /// Получает размер поддерева по индексу корня (0 для `NULL`).
+    fn size(&self, idx: u32) -> u32 {
+        if idx == NULL {
+            0
+        } else {
+            self.node(idx).size
+        }
+    }
+
+    /// Обновляет высоту и размер узла по его непосредственным потомкам.
+    fn update_stats(&mut self, idx: u32) {
+        let (left, right) = {
+            let n = self.node(idx);
+            (n.left, n.right)
+        };
+        let height = 1 + self.height(left).max(self.height(right));
+        let size = 1 + self.size(left) + self.size(right);
+        let n = self.node_mut(idx);
+        n.height = height;
+        n.size = size;
+    }
+
+    /// Баланс-фактор узла.
+    fn balance_factor(&self, idx: u32) -> i64 {
+        let n = self.node(idx);
+        self.height(n.left) as i64 - self.height(n.right) as i64
+    }
+
+    /// Выполняет правый поворот вокруг `idx`, возвращает индекс нового корня.
+    fn rotate_right(&mut self, idx: u32) -> u32 {
+        let new_root = self.node(idx).left;
+        let new_root_right = self.node(new_root).right;
+        self.node_mut(idx).left = new_root_right;
+        self.update_stats(idx);
+        self.node_mut(new_root).right = idx;
+        self.update_stats(new_root);
         new_root
     }
 
-    /// Выполняет левый поворот.
-    fn rotate_left(mut self: Box<Self>) -> Box<Self> {
-        let mut new_root = self
-            .right
-            .take()
-            .expect("Правый дочерний узел должен существовать");
-        self.right = new_root.left.take();
-        self.update_height();
-        new_root.left = Some(self);
-        new_root.update_height();
+    /// Выполняет левый поворот вокруг `idx`, возвращает индекс нового корня.
+    fn rotate_left(&mut self, idx: u32) -> u32 {
+        let new_root = self.node(idx).right;
+        let new_root_left = self.node(new_root).left;
+        self.node_mut(idx).right = new_root_left;
+        self.update_stats(idx);
+        self.node_mut(new_root).left = idx;
+        self.update_stats(new_root);
         new_root
     }
 
-    /// Балансирует узел.
-    fn balance(mut self: Box<Self>) -> Box<Self> {
-        self.update_height();
-        let balance = self.balance_factor();
+    /// Балансирует узел, возвращает индекс корня (возможно, другого узла).
+    fn balance(&mut self, idx: u32) -> u32 {
+        self.update_stats(idx);
+        let balance = self.balance_factor(idx);
 
         // Левая тяжесть
         if balance > 1 {
-            if self.left.as_ref().unwrap().balance_factor() < 0 {
-                self.left = self.left.take().map(|left| left.rotate_left());
+            let left = self.node(idx).left;
+            if self.balance_factor(left) < 0 {
+                let new_left = self.rotate_left(left);
+                self.node_mut(idx).left = new_left;
             }
-            return self.rotate_right();
+            return self.rotate_right(idx);
         }
 
         // Правая тяжесть
         if balance < -1 {
-            if self.right.as_ref().unwrap().balance_factor() > 0 {
-                self.right = self.right.take().map(|right| right.rotate_right());
+            let right = self.node(idx).right;
+            if self.balance_factor(right) > 0 {
+                let new_right = self.rotate_right(right);
+                self.node_mut(idx).right = new_right;
             }
-            return self.rotate_left();
+            return self.rotate_left(idx);
         }
 
-        self
+        idx
     }
 
-    /// Вставляет новый узел в поддерево.
-    fn insert(mut self: Box<Self>, key: K, value: V) -> Box<Self> {
-        match key.cmp(&self.key) {
+    /// Вставляет узел с заданным ключом и значением в поддерево `idx`.
+    fn insert_at(&mut self, idx: u32, key: K, value: V) -> u32 {
+        if idx == NULL {
+            return self.alloc(key, value);
+        }
+        match key.cmp(&self.node(idx).key) {
             Ordering::Less => {
-                if let Some(left) = self.left.take() {
-                    self.left = Some(left.insert(key, value));
-                } else {
-                    self.left = Some(Box::new(Node::new(key, value)));
-                }
+                let left = self.node(idx).left;
+                let new_left = self.insert_at(left, key, value);
+                self.node_mut(idx).left = new_left;
             }
             Ordering::Greater => {
-                if let Some(right) = self.right.take() {
-                    self.right = Some(right.insert(key, value));
-                } else {
-                    self.right = Some(Box::new(Node::new(key, value)));
-                }
+                let right = self.node(idx).right;
+                let new_right = self.insert_at(right, key, value);
+                self.node_mut(idx).right = new_right;
             }
             Ordering::Equal => {
-                self.value = value;
+                self.node_mut(idx).value = value;
+                return idx;
             }
         }
-        self.balance()
+        self.balance(idx)
+    }
+
+    /// Вставляет узел с заданным ключом и значением.
+    ///
+    /// Если узел с таким ключом уже существует, его значение обновляется.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.root = self.insert_at(self.root, key, value);
     }
 
-    /// Находит минимальный узел в поддереве.
-    fn find_min(node: Box<Self>) -> Box<Self> {
-        match node.left {
-            Some(left) => Node::find_min(left),
-            None => node,
+    /// Удаляет минимальный узел поддерева `idx`, возвращая новый корень
+    /// поддерева и ключ/значение удаленного узла.
+    fn remove_min(&mut self, idx: u32) -> (u32, K, V) {
+        let left = self.node(idx).left;
+        if left == NULL {
+            let right = self.node(idx).right;
+            let node = self.take_node(idx);
+            return (right, node.key, node.value);
         }
+        let (new_left, key, value) = self.remove_min(left);
+        self.node_mut(idx).left = new_left;
+        (self.balance(idx), key, value)
     }
 
-    /// Удаляет узел с заданным ключом из поддерева.
-    fn remove(mut self: Box<Self>, key: &K) -> Option<Box<Self>> {
-        match key.cmp(&self.key) {
+    /// Удаляет узел с заданным ключом из поддерева `idx`.
+    fn remove_at(&mut self, idx: u32, key: &K) -> u32 {
+        if idx == NULL {
+            return NULL;
+        }
+        match key.cmp(&self.node(idx).key) {
             Ordering::Less => {
-                if let Some(left) = self.left.take() {
-                    self.left = left.remove(key);
-                }
+                let left = self.node(idx).left;
+                let new_left = self.remove_at(left, key);
+                self.node_mut(idx).left = new_left;
             }
             Ordering::Greater => {
-                if let Some(right) = self.right.take() {
-                    self.right = right.remove(key);
-                }
+                let right = self.node(idx).right;
+                let new_right = self.remove_at(right, key);
+                self.node_mut(idx).right = new_right;
             }
             Ordering::Equal => {
-                if self.left.is_none() {
-                    return self.right;
+                let left = self.node(idx).left;
+                let right = self.node(idx).right;
+                if left == NULL {
+                    self.take_node(idx);
+                    return right;
                 }
-                if self.right.is_none() {
-                    return self.left;
+                if right == NULL {
+                    self.take_node(idx);
+                    return left;
                 }
-                let min = Node::find_min(self.right.take().unwrap());
-                self.key = min.key;
-                self.value = min.value;
-                self.right = min.right;
-                self.left = min.left;
+                let (new_right, min_key, min_value) = self.remove_min(right);
+                let n = self.node_mut(idx);
+                n.key = min_key;
+                n.value = min_value;
+                n.right = new_right;
             }
         }
-        Some(self.balance())
+        self.balance(idx)
     }
 
-    /// Ищет узел с заданным ключом.
-    fn find(&self, key: &K) -> Option<&V> {
-        match key.cmp(&self.key) {
-            Ordering::Less => self.left.as_ref().and_then(|left| left.find(key)),
-            Ordering::Greater => self.right.as_ref().and_then(|right| right.find(key)),
-            Ordering::Equal => Some(&self.value),
+    /// Удаляет узел с заданным ключом.
+    ///
+    /// Возвращает `true`, если узел был найден и удален, иначе `false`.
+    pub fn remove(&mut self, key: &K) -> bool {
+        if self.root == NULL {
+            false
+        } else {
+            self.root = self.remove_at(self.root, key);
+            true
         }
     }
 
-    /// Итерация по узлам поддерева.
-    fn inorder_traversal(&self, visit: &mut dyn FnMut(&K, &V)) {
-        if let Some(ref left) = self.left {
-            left.inorder_traversal(visit);
+    /// Ищет значение по заданному ключу в поддереве `idx`.
+    fn find_at(&self, idx: u32, key: &K) -> Option<&V> {
+        if idx == NULL {
+            return None;
         }
-        visit(&self.key, &self.value);
-        if let Some(ref right) = self.right {
-            right.inorder_traversal(visit);
+        match key.cmp(&self.node(idx).key) {
+            Ordering::Less => self.find_at(self.node(idx).left, key),
+            Ordering::Greater => self.find_at(self.node(idx).right, key),
+            Ordering::Equal => Some(&self.node(idx).value),
         }
     }
-}
 
-/// Структура AVL-дерева.
-pub struct AVLTree<K, V> {
-    root: Option<Box<Node<K, V>>>,
-}
+    /// Ищет значение по заданному ключу.
+    ///
+    /// Возвращает ссылку на значение, если ключ найден, иначе `None`.
+    pub fn find(&self, key: &K) -> Option<&V> {
+        self.find_at(self.root, key)
+    }
 
-impl<K: Ord + Debug, V: Debug> AVLTree<K, V> {
-    /// Создает новое пустое AVL-дерево.
-    pub fn new() -> Self {
-        AVLTree { root: None }
+    /// Возвращает количество элементов в дереве.
+    pub fn len(&self) -> usize {
+        self.size(self.root) as usize
     }
 
-    /// Вставляет узел с заданным ключом и значением.
+    /// Возвращает `true`, если дерево не содержит элементов.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Возвращает k-й по возрастанию ключ/значение (0-индексация) в поддереве `idx`.
+    fn select_at(&self, idx: u32, k: u32) -> Option<(&K, &V)> {
+        if idx == NULL {
+            return None;
+        }
+        let n = self.node(idx);
+        let left_size = self.size(n.left);
+        match k.cmp(&left_size) {
+            Ordering::Less => self.select_at(n.left, k),
+            Ordering::Equal => Some((&n.key, &n.value)),
+            Ordering::Greater => self.select_at(n.right, k - left_size - 1),
+        }
+    }
+
+    /// Возвращает k-й по возрастанию ключ/значение (0-индексация).
     ///
-    /// Если узел с таким ключом уже существует, его значение обновляется.
-    pub fn insert(&mut self, key: K, value: V) {
-        if let Some(root) = self.root.take() {
-            self.root = Some(root.insert(key, value));
+    /// Возвращает `None`, если `k` выходит за пределы дерева.
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        if k >= self.len() {
+            return None;
+        }
+        self.select_at(self.root, k as u32)
+    }
+
+    /// Считает количество ключей в поддереве `idx`, строго меньших заданного.
+    fn rank_at(&self, idx: u32, key: &K) -> u32 {
+        if idx == NULL {
+            return 0;
+        }
+        let n = self.node(idx);
+        match key.cmp(&n.key) {
+            Ordering::Greater => self.size(n.left) + 1 + self.rank_at(n.right, key),
+            Ordering::Less => self.rank_at(n.left, key),
+            Ordering::Equal => self.size(n.left),
+        }
+    }
+
+    /// Считает количество ключей в дереве, строго меньших заданного.
+    pub fn rank(&self, key: &K) -> usize {
+        self.rank_at(self.root, key) as usize
+    }
+
+    /// Возвращает пару ключ/значение с минимальным ключом поддерева `idx`.
+    fn min_at(&self, idx: u32) -> (&K, &V) {
+        let left = self.node(idx).left;
+        if left == NULL {
+            let n = self.node(idx);
+            (&n.key, &n.value)
         } else {
-            self.root = Some(Box::new(Node::new(key, value)));
+            self.min_at(left)
         }
     }
 
-    /// Удаляет узел с заданным ключом.
-    ///
-    /// Возвращает `true`, если узел был найден и удален, иначе `false`.
-    pub fn remove(&mut self, key: &K) -> bool {
-        if let Some(root) = self.root.take() {
-            let new_root = root.remove(key);
-            self.root = new_root;
-            true
+    /// Возвращает пару ключ/значение с максимальным ключом поддерева `idx`.
+    fn max_at(&self, idx: u32) -> (&K, &V) {
+        let right = self.node(idx).right;
+        if right == NULL {
+            let n = self.node(idx);
+            (&n.key, &n.value)
         } else {
-            false
+            self.max_at(right)
         }
     }
 
-    /// Ищет значение по заданному ключу.
+    /// Возвращает пару ключ/значение с минимальным ключом.
+    pub fn min(&self) -> Option<(&K, &V)> {
+        if self.root == NULL {
+            None
+        } else {
+            Some(self.min_at(self.root))
+        }
+    }
+
+    /// Возвращает пару ключ/значение с максимальным ключом.
+    pub fn max(&self) -> Option<(&K, &V)> {
+        if self.root == NULL {
+            None
+        } else {
+            Some(self.max_at(self.root))
+        }
+    }
+
+    /// Находит наибольший ключ, не превышающий заданный, в поддереве `idx`.
+    fn floor_at(&self, idx: u32, key: &K) -> Option<(&K, &V)> {
+        if idx == NULL {
+            return None;
+        }
+        let n = self.node(idx);
+        match key.cmp(&n.key) {
+            Ordering::Equal => Some((&n.key, &n.value)),
+            Ordering::Less => self.floor_at(n.left, key),
+            Ordering::Greater => self.floor_at(n.right, key).or(Some((&n.key, &n.value))),
+        }
+    }
+
+    /// Находит наименьший ключ, не меньший заданного, в поддереве `idx`.
+    fn ceil_at(&self, idx: u32, key: &K) -> Option<(&K, &V)> {
+        if idx == NULL {
+            return None;
+        }
+        let n = self.node(idx);
+        match key.cmp(&n.key) {
+            Ordering::Equal => Some((&n.key, &n.value)),
+            Ordering::Greater => self.ceil_at(n.right, key),
+            Ordering::Less => self.ceil_at(n.left, key).or(Some((&n.key, &n.value))),
+        }
+    }
+
+    /// Находит наибольший ключ, не превышающий заданный (и связанное с ним значение).
+    pub fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        self.floor_at(self.root, key)
+    }
+
+    /// Находит наименьший ключ, не меньший заданного (и связанное с ним значение).
+    pub fn ceil(&self, key: &K) -> Option<(&K, &V)> {
+        self.ceil_at(self.root, key)
+    }
+
+    /// Возвращает ленивый итератор по парам ключ/значение, ключи которых
+    /// попадают в границы `r`, в порядке возрастания ключей.
     ///
-    /// Возвращает ссылку на значение, если ключ найден, иначе `None`.
-    pub fn find(&self, key: &K) -> Option<&V> {
-        self.root.as_ref().and_then(|root| root.find(key))
+    /// Поддеревья целиком вне границ не посещаются, поэтому запрос работает
+    /// за `O(log n + k)`, где `k` — число найденных элементов.
+    pub fn range<R: RangeBounds<K>>(&self, r: R) -> Range<'_, K, V, R> {
+        Range::new(self, r)
+    }
+
+    /// Возвращает итератор по парам ключ/значение в порядке возрастания ключей.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(self)
     }
 
-    /// Выполняет итерацию по всем узлам дерева в порядке возрастания ключей.
+    /// Собирает индексы узлов поддерева `idx` в порядке возрастания ключей.
+    fn inorder_indices_at(&self, idx: u32, out: &mut Vec<u32>) {
+        if idx == NULL {
+            return;
+        }
+        self.inorder_indices_at(self.node(idx).left, out);
+        out.push(idx);
+        self.inorder_indices_at(self.node(idx).right, out);
+    }
+
+    /// Собирает индексы всех узлов дерева в порядке возрастания ключей.
+    fn inorder_indices(&self) -> Vec<u32> {
+        let mut out = Vec::new();
+        self.inorder_indices_at(self.root, &mut out);
+        out
+    }
+
+    /// Возвращает итератор по парам ключ/изменяемое значение в порядке
+    /// возрастания ключей.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let order = self.inorder_indices();
+        let mut slots: Vec<Option<&mut Node<K, V>>> =
+            self.pool.iter_mut().map(|slot| slot.as_mut()).collect();
+        let items: Vec<(&K, &mut V)> = order
+            .into_iter()
+            .map(|idx| {
+                let node = slots[idx as usize]
+                    .take()
+                    .expect("индекс должен указывать на занятый слот пула");
+                (&node.key, &mut node.value)
+            })
+            .collect();
+        IterMut {
+            inner: items.into_iter(),
+        }
+    }
+
+    /// Рекурсивно печатает поддерево `idx`, повернутое на 90° (правый
+    /// потомок сверху, левый снизу), накапливая отступ `prefix` и
+    /// выбирая коннектор по тому, левый это потомок или правый.
+    fn display_tree_at(&self, idx: u32, prefix: &str, is_left: bool, out: &mut String) {
+        if idx == NULL {
+            return;
+        }
+        let (right, left, label) = {
+            let n = self.node(idx);
+            (n.right, n.left, format!("{:?}: {:?}", n.key, n.value))
+        };
+
+        if right != NULL {
+            let child_prefix = format!("{prefix}{}", if is_left { "│   " } else { "    " });
+            self.display_tree_at(right, &child_prefix, false, out);
+        }
+
+        out.push_str(prefix);
+        out.push_str(if is_left { "└───" } else { "┌───" });
+        out.push_str(&label);
+        out.push('\n');
+
+        if left != NULL {
+            let child_prefix = format!("{prefix}{}", if is_left { "    " } else { "│   " });
+            self.display_tree_at(left, &child_prefix, true, out);
+        }
+    }
+
+    /// Рисует дерево в виде ASCII/Unicode-диаграммы со связями `┌───`/`└───`/`│`,
+    /// повернутой на 90°: корень у левого края, правое поддерево выше корня,
+    /// левое — ниже.
+    pub fn display_tree(&self) -> String {
+        let mut out = String::new();
+        self.display_tree_at(self.root, "", true, &mut out);
+        out
+    }
+
+    /// Строит сбалансированное поддерево из среза `items[lo..hi]`, беря
+    /// средний элемент корнем и рекурсивно строя половины — высота
+    /// получается минимальной без единого поворота.
+    fn build_sorted(&mut self, items: &mut [Option<(K, V)>], lo: usize, hi: usize) -> u32 {
+        if lo >= hi {
+            return NULL;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let left = self.build_sorted(items, lo, mid);
+        let right = self.build_sorted(items, mid + 1, hi);
+        let (key, value) = items[mid].take().expect("элемент уже был использован");
+        let idx = self.alloc(key, value);
+        {
+            let n = self.node_mut(idx);
+            n.left = left;
+            n.right = right;
+        }
+        self.update_stats(idx);
+        idx
+    }
+
+    /// Строит дерево напрямую из отсортированной по возрастанию ключей и
+    /// лишенной дубликатов последовательности пар ключ/значение за `O(n)`,
+    /// без отдельных вызовов `insert`.
     ///
-    /// Функция `visit` вызывается для каждого узла с ссылками на его ключ и значение.
-    pub fn inorder_traversal<F>(&self, mut visit: F)
-    where
-        F: FnMut(&K, &V),
-    {
-        if let Some(ref root) = self.root {
-            root.inorder_traversal(&mut visit);
+    /// Вызывающая сторона отвечает за то, что `sorted` уже отсортирован по
+    /// ключу и не содержит дубликатов — для произвольных данных собирайте
+    /// дерево через `FromIterator`, который сам сортирует и дедуплицирует.
+    pub fn from_sorted(sorted: Vec<(K, V)>) -> Self {
+        let mut tree = AVLTree::new();
+        tree.pool.reserve(sorted.len());
+        let mut items: Vec<Option<(K, V)>> = sorted.into_iter().map(Some).collect();
+        let len = items.len();
+        tree.root = tree.build_sorted(&mut items, 0, len);
+        tree
+    }
+
+    /// Сортирует пары по ключу и убирает дубликаты, оставляя для каждого
+    /// ключа последнее по исходному порядку значение — так же, как
+    /// повторный `insert` с тем же ключом обновляет значение.
+    fn sort_dedup_keep_last(mut items: Vec<(K, V)>) -> Vec<(K, V)> {
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut result: Vec<(K, V)> = Vec::with_capacity(items.len());
+        for item in items {
+            if result.last().is_some_and(|last: &(K, V)| last.0 == item.0) {
+                result.pop();
+            }
+            result.push(item);
+        }
+        result
+    }
+}
+
+impl<K: Ord + Debug + Serialize, V: Debug + Serialize> AVLTree<K, V> {
+    /// Сериализует дерево и сохраняет его в файл по пути `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let bytes = bincode::serialize(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        std::fs::write(path, bytes)
+    }
+}
+
+impl<K, V> AVLTree<K, V>
+where
+    K: Ord + Debug + for<'de> Deserialize<'de>,
+    V: Debug + for<'de> Deserialize<'de>,
+{
+    /// Загружает дерево, ранее сохраненное [`AVLTree::save`], из файла по пути `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        bincode::deserialize(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl<K: Ord + Debug + Serialize, V: Debug + Serialize> Serialize for AVLTree<K, V> {
+    /// Сериализует не форму узлов, а отсортированную последовательность
+    /// пар ключ/значение — так десериализация воссоздает дерево за `O(n)`
+    /// и гарантированно сбалансированным.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for pair in self.iter() {
+            seq.serialize_element(&pair)?;
         }
+        seq.end()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for AVLTree<K, V>
+where
+    K: Ord + Debug + Deserialize<'de>,
+    V: Debug + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sorted = Vec::<(K, V)>::deserialize(deserializer)?;
+        Ok(AVLTree::from_sorted(sorted))
     }
 }
 
@@ -239,6 +617,195 @@ impl<K: Ord + Debug, V: Debug> Default for AVLTree<K, V> {
     }
 }
 
+/// Ленивый итератор по парам ключ/значение в порядке возрастания ключей.
+///
+/// Реализован на явном стеке индексов узлов (левая ветвь заранее дожата до
+/// упора), поэтому не рекурсивен и поддерживает досрочную остановку —
+/// `take`, `find`, ранний `break` и т.п. не обходят все дерево.
+pub struct Iter<'a, K, V> {
+    tree: &'a AVLTree<K, V>,
+    stack: Vec<u32>,
+}
+
+impl<'a, K: Ord + Debug, V: Debug> Iter<'a, K, V> {
+    fn new(tree: &'a AVLTree<K, V>) -> Self {
+        let mut iter = Iter {
+            tree,
+            stack: Vec::new(),
+        };
+        iter.push_left_spine(tree.root);
+        iter
+    }
+
+    fn push_left_spine(&mut self, mut idx: u32) {
+        while idx != NULL {
+            self.stack.push(idx);
+            idx = self.tree.node(idx).left;
+        }
+    }
+}
+
+impl<'a, K: Ord + Debug, V: Debug> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.stack.pop()?;
+        self.push_left_spine(self.tree.node(idx).right);
+        let n = self.tree.node(idx);
+        Some((&n.key, &n.value))
+    }
+}
+
+/// Итератор по парам ключ/изменяемое значение в порядке возрастания ключей.
+///
+/// Собирается заранее в вектор: для этого индексы узлов обходятся в
+/// порядке возрастания ключей (immutable проход), а затем по одному
+/// разбираются из `pool.iter_mut()`, что безопасно разносит изменяемые
+/// ссылки на разные слоты без unsafe-кода.
+pub struct IterMut<'a, K, V> {
+    inner: std::vec::IntoIter<(&'a K, &'a mut V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Итератор, потребляющий дерево и отдающий пары ключ/значение по владению.
+pub struct IntoIter<K, V> {
+    inner: std::vec::IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Ленивый итератор по парам ключ/значение, попадающим в границы диапазона.
+///
+/// Использует тот же стек индексов, что и [`Iter`], но наполняет его
+/// выборочно: поддеревья, целиком лежащие левее нижней границы, в стек не
+/// попадают, а как только со стека снимается узел правее верхней границы,
+/// обход останавливается — оставшаяся часть дерева заведомо вне диапазона.
+pub struct Range<'a, K, V, R> {
+    tree: &'a AVLTree<K, V>,
+    stack: Vec<u32>,
+    bounds: R,
+}
+
+impl<'a, K: Ord + Debug, V: Debug, R: RangeBounds<K>> Range<'a, K, V, R> {
+    fn new(tree: &'a AVLTree<K, V>, bounds: R) -> Self {
+        let mut iter = Range {
+            tree,
+            stack: Vec::new(),
+            bounds,
+        };
+        iter.push_left_spine(tree.root);
+        iter
+    }
+
+    fn after_start(&self, key: &K) -> bool {
+        match self.bounds.start_bound() {
+            Bound::Included(start) => key >= start,
+            Bound::Excluded(start) => key > start,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn before_end(&self, key: &K) -> bool {
+        match self.bounds.end_bound() {
+            Bound::Included(end) => key <= end,
+            Bound::Excluded(end) => key < end,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn push_left_spine(&mut self, mut idx: u32) {
+        while idx != NULL {
+            let n = self.tree.node(idx);
+            if self.after_start(&n.key) {
+                self.stack.push(idx);
+                idx = n.left;
+            } else {
+                idx = n.right;
+            }
+        }
+    }
+}
+
+impl<'a, K: Ord + Debug, V: Debug, R: RangeBounds<K>> Iterator for Range<'a, K, V, R> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.stack.pop()?;
+        let n = self.tree.node(idx);
+        if !self.before_end(&n.key) {
+            self.stack.clear();
+            return None;
+        }
+        self.push_left_spine(n.right);
+        Some((&n.key, &n.value))
+    }
+}
+
+impl<'a, K: Ord + Debug, V: Debug> IntoIterator for &'a AVLTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: Ord + Debug, V: Debug> IntoIterator for &'a mut AVLTree<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> IntoIterator for AVLTree<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let order = self.inorder_indices();
+        let mut out = Vec::with_capacity(order.len());
+        for idx in order {
+            let node = self.take_node(idx);
+            out.push((node.key, node.value));
+        }
+        IntoIter {
+            inner: out.into_iter(),
+        }
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> FromIterator<(K, V)> for AVLTree<K, V> {
+    /// Строит дерево за один проход вместо `N` отдельных `insert`: сначала
+    /// сортирует и дедуплицирует пары, затем вызывает `from_sorted`.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let items: Vec<(K, V)> = iter.into_iter().collect();
+        AVLTree::from_sorted(AVLTree::sort_dedup_keep_last(items))
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> Extend<(K, V)> for AVLTree<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,20 +834,201 @@ mod tests {
         assert!(tree.remove(&10));
         assert!(tree.remove(&100));
     }
+
     #[test]
-    fn test_inorder_traversal() {
+    fn test_remove_reuses_freed_slots() {
+        let mut tree = AVLTree::new();
+        for i in 0..10 {
+            tree.insert(i, i.to_string());
+        }
+        for i in 0..10 {
+            tree.remove(&i);
+        }
+        assert_eq!(tree.len(), 0);
+
+        for i in 100..105 {
+            tree.insert(i, i.to_string());
+        }
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.pool.len(), 10);
+        assert_eq!(tree.find(&102), Some(&"102".to_string()));
+    }
+
+    #[test]
+    fn test_iter() {
         let mut tree = AVLTree::new();
         let elements = vec![(10, "a"), (20, "b"), (5, "c"), (15, "d")];
         for &(k, v) in &elements {
             tree.insert(k, v);
         }
 
-        let mut result = Vec::new();
-        tree.inorder_traversal(|k, v| result.push((*k, *v)));
+        let result: Vec<(i32, &str)> = tree.iter().map(|(&k, &v)| (k, v)).collect();
         let expected = vec![(5, "c"), (10, "a"), (15, "d"), (20, "b")];
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_iter_mut() {
+        let mut tree = AVLTree::new();
+        tree.insert(10, 1);
+        tree.insert(20, 2);
+        tree.insert(5, 3);
+
+        for (_, value) in tree.iter_mut() {
+            *value *= 10;
+        }
+
+        let result: Vec<(i32, i32)> = tree.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(result, vec![(5, 30), (10, 10), (20, 20)]);
+    }
+
+    #[test]
+    fn test_into_iter_and_from_iter() {
+        let mut tree = AVLTree::new();
+        tree.insert(10, "a");
+        tree.insert(20, "b");
+        tree.insert(5, "c");
+
+        let collected: Vec<(i32, &str)> = tree.into_iter().collect();
+        assert_eq!(collected, vec![(5, "c"), (10, "a"), (20, "b")]);
+
+        let rebuilt: AVLTree<i32, &str> = collected.into_iter().collect();
+        assert_eq!(rebuilt.find(&10), Some(&"a"));
+        assert_eq!(rebuilt.len(), 3);
+    }
+
+    #[test]
+    fn test_from_sorted() {
+        let sorted = vec![(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")];
+        let tree = AVLTree::from_sorted(sorted);
+
+        assert_eq!(tree.len(), 5);
+        let result: Vec<(i32, &str)> = tree.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(
+            result,
+            vec![(1, "a"), (2, "b"), (3, "c"), (4, "d"), (5, "e")]
+        );
+    }
+
+    #[test]
+    fn test_from_iter_sorts_and_dedups_keeping_last() {
+        let tree: AVLTree<i32, &str> =
+            vec![(20, "b"), (10, "a"), (5, "c"), (10, "updated")]
+                .into_iter()
+                .collect();
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.find(&10), Some(&"updated"));
+        let result: Vec<(i32, &str)> = tree.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(result, vec![(5, "c"), (10, "updated"), (20, "b")]);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut tree = AVLTree::new();
+        tree.insert(10, "a");
+        tree.extend(vec![(20, "b"), (5, "c")]);
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.find(&20), Some(&"b"));
+    }
+
+    #[test]
+    fn test_select_and_rank() {
+        let mut tree = AVLTree::new();
+        let elements = vec![(10, "a"), (20, "b"), (5, "c"), (15, "d")];
+        for &(k, v) in &elements {
+            tree.insert(k, v);
+        }
+
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.select(0), Some((&5, &"c")));
+        assert_eq!(tree.select(1), Some((&10, &"a")));
+        assert_eq!(tree.select(2), Some((&15, &"d")));
+        assert_eq!(tree.select(3), Some((&20, &"b")));
+        assert_eq!(tree.select(4), None);
+
+        assert_eq!(tree.rank(&5), 0);
+        assert_eq!(tree.rank(&10), 1);
+        assert_eq!(tree.rank(&15), 2);
+        assert_eq!(tree.rank(&20), 3);
+        assert_eq!(tree.rank(&1), 0);
+        assert_eq!(tree.rank(&100), 4);
+    }
+
+    #[test]
+    fn test_min_max_floor_ceil() {
+        let mut tree = AVLTree::new();
+        let elements = vec![(10, "a"), (20, "b"), (5, "c"), (15, "d")];
+        for &(k, v) in &elements {
+            tree.insert(k, v);
+        }
+
+        assert_eq!(tree.min(), Some((&5, &"c")));
+        assert_eq!(tree.max(), Some((&20, &"b")));
+
+        assert_eq!(tree.floor(&10), Some((&10, &"a")));
+        assert_eq!(tree.floor(&12), Some((&10, &"a")));
+        assert_eq!(tree.floor(&1), None);
+
+        assert_eq!(tree.ceil(&10), Some((&10, &"a")));
+        assert_eq!(tree.ceil(&12), Some((&15, &"d")));
+        assert_eq!(tree.ceil(&100), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut tree = AVLTree::new();
+        let elements = vec![(10, "a"), (20, "b"), (5, "c"), (15, "d"), (25, "e")];
+        for &(k, v) in &elements {
+            tree.insert(k, v);
+        }
+
+        let result: Vec<(i32, &str)> = tree.range(10..=20).map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(result, vec![(10, "a"), (15, "d"), (20, "b")]);
+
+        let result: Vec<(i32, &str)> = tree.range(..).map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(
+            result,
+            vec![(5, "c"), (10, "a"), (15, "d"), (20, "b"), (25, "e")]
+        );
+    }
+
+    #[test]
+    fn test_display_tree() {
+        let mut tree = AVLTree::new();
+        tree.insert(10, "a");
+        tree.insert(20, "b");
+        tree.insert(5, "c");
+
+        let rendered = tree.display_tree();
+        assert!(rendered.contains("10: \"a\""));
+        assert!(rendered.contains("┌───20: \"b\""));
+        assert!(rendered.contains("└───5: \"c\""));
+
+        let empty: AVLTree<i32, &str> = AVLTree::new();
+        assert_eq!(empty.display_tree(), "");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut tree = AVLTree::new();
+        tree.insert(10, "a".to_string());
+        tree.insert(20, "b".to_string());
+        tree.insert(5, "c".to_string());
+
+        let path = std::env::temp_dir().join("avl_tree_test_save_and_load.bin");
+        tree.save(&path).unwrap();
+
+        let loaded: AVLTree<i32, String> = AVLTree::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        let expected: Vec<(i32, String)> = tree.iter().map(|(&k, v)| (k, v.clone())).collect();
+        let actual: Vec<(i32, String)> = loaded.iter().map(|(&k, v)| (k, v.clone())).collect();
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_update_value() {
         let mut tree = AVLTree::new();
@@ -12,6 +12,9 @@ fn main() {
     println!("  remove <ключ>             - Удалить элемент");
     println!("  find <ключ>               - Найти значение по ключу");
     println!("  display                   - Вывести все элементы в порядке возрастания ключей");
+    println!("  display_tree              - Нарисовать структуру дерева");
+    println!("  save <файл>               - Сохранить дерево в файл");
+    println!("  load <файл>               - Загрузить дерево из файла");
     println!("  exit                      - Выйти из программы");
 
     loop {
@@ -90,7 +93,39 @@ fn main() {
             }
             "display" => {
                 println!("Элементы AVL-дерева (в порядке возрастания ключей):");
-                tree.inorder_traversal(|k, v| println!("  Ключ: {}, Значение: {}", k, v));
+                for (k, v) in tree.iter() {
+                    println!("  Ключ: {}, Значение: {}", k, v);
+                }
+            }
+            "display_tree" => {
+                if tree.is_empty() {
+                    println!("Дерево пусто.");
+                } else {
+                    print!("{}", tree.display_tree());
+                }
+            }
+            "save" => {
+                if parts.len() != 2 {
+                    println!("Недостаточно аргументов для команды save. Использование: save <файл>");
+                    continue;
+                }
+                match tree.save(parts[1]) {
+                    Ok(()) => println!("Дерево сохранено в файл {}.", parts[1]),
+                    Err(err) => println!("Ошибка сохранения: {}", err),
+                }
+            }
+            "load" => {
+                if parts.len() != 2 {
+                    println!("Недостаточно аргументов для команды load. Использование: load <файл>");
+                    continue;
+                }
+                match AVLTree::load(parts[1]) {
+                    Ok(loaded) => {
+                        tree = loaded;
+                        println!("Дерево загружено из файла {}.", parts[1]);
+                    }
+                    Err(err) => println!("Ошибка загрузки: {}", err),
+                }
             }
             "exit" => {
                 println!("Йоу, вассап, заходите еще!");